@@ -51,6 +51,58 @@
 //! send and receive messages.
 //!
 //! [native messaging documentation]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging
+//!
+//! ## Beyond stdin/stdout
+//!
+//! [`read_message`]/[`write_message`] are thin wrappers around
+//! [`read_message_from`]/[`write_message_to`], which frame messages over any
+//! [`std::io::Read`]/[`std::io::Write`] — a socket, a pipe, a subprocess's
+//! stdio, or an in-memory buffer in tests.
+//!
+//! For the common "read a message, reply, repeat until the extension
+//! disconnects" loop, [`messages`]/[`messages_from`] return an iterator that
+//! ends with `None` on a clean EOF instead of an error:
+//!
+//! ```rust,no_run
+//! use web_ext_native_messaging::messages;
+//!
+//! for message in messages::<String>() {
+//!   println!("{}", message.unwrap());
+//! }
+//! ```
+//!
+//! ## Ad-hoc messages
+//!
+//! [`send!`] and [`send_to!`] build a [`serde_json::Value`] with
+//! [`serde_json::json!`] and write it as a message, for quick replies that
+//! don't warrant a dedicated struct:
+//!
+//! ```rust,no_run
+//! use web_ext_native_messaging::send;
+//!
+//! send!({ "status": "ok" }).unwrap();
+//! ```
+//!
+//! ## Message size limit
+//!
+//! Messages are capped at [`MAX_MESSAGE_LENGTH`] (1 MiB), matching the limit
+//! enforced by the browser's native messaging implementation; exceeding it
+//! returns [`MessagingError::MessageTooLarge`]. Use the `_with_limit`
+//! functions to raise or lower the cap.
+//!
+//! ## Other serialization formats
+//!
+//! The framing is independent of the payload encoding. JSON is the default
+//! via the [`Json`] [`Codec`], but [`read_message_from_with_codec`]/
+//! [`write_message_to_with_codec`] also support CBOR and FlexBuffers behind
+//! the `cbor` and `flexbuffers` feature flags, for two cooperating processes
+//! that both use this crate to exchange more compact binary messages.
+//!
+//! ## Async
+//!
+//! The `async` feature adds `async_read_message`/`async_write_message` (and
+//! their `_from`/`_to` variants) over `tokio::io::AsyncRead`/
+//! `tokio::io::AsyncWrite`, for hosts that need to do concurrent I/O.
 
 use std::{
   convert::TryInto,
@@ -74,21 +126,200 @@ pub enum MessagingError {
   /// Integer parsing errors.
   #[error(transparent)]
   TryFromInt(#[from] std::num::TryFromIntError),
+  /// CBOR (de)serialization errors.
+  #[cfg(feature = "cbor")]
+  #[error(transparent)]
+  Cbor(#[from] serde_cbor::Error),
+  /// FlexBuffers (de)serialization errors.
+  #[cfg(feature = "flexbuffers")]
+  #[error("flexbuffers error: {0}")]
+  FlexBuffers(String),
+  /// The message's length exceeded the configured limit, either while
+  /// reading a message from the extension or while writing one to it.
+  #[error("message length {length} exceeds the limit of {limit} bytes")]
+  MessageTooLarge {
+    /// The length of the oversized message, in bytes.
+    length: u32,
+    /// The limit that `length` exceeded, in bytes.
+    limit: u32,
+  },
+}
+
+/// The maximum length of a single message allowed by the browser's native
+/// messaging protocol (1 MiB), as documented for both Firefox and Chrome.
+pub const MAX_MESSAGE_LENGTH: u32 = 1_048_576;
+
+/// A (de)serialization format for the message body, independent of the
+/// length-prefixed framing.
+///
+/// Implementations are zero-sized marker types passed as a type parameter
+/// to [`read_message_from_with_codec`]/[`write_message_to_with_codec`], so
+/// two cooperating native-messaging processes can agree on a codec other
+/// than JSON while keeping the same framing.
+pub trait Codec {
+  /// Serializes `message` into a byte buffer.
+  fn to_vec<S>(message: &S) -> Result<Vec<u8>, MessagingError>
+  where
+    S: serde::Serialize;
+
+  /// Deserializes a value out of `reader`.
+  fn from_reader<D, R>(reader: R) -> Result<D, MessagingError>
+  where
+    D: for<'a> serde::Deserialize<'a>,
+    R: Read;
+}
+
+/// The default [`Codec`], encoding messages as JSON. This is the format
+/// expected on the browser-facing end of native messaging.
+#[derive(Debug, Default)]
+pub struct Json;
+
+impl Codec for Json {
+  fn to_vec<S>(message: &S) -> Result<Vec<u8>, MessagingError>
+  where
+    S: serde::Serialize,
+  {
+    serde_json::to_vec(message).map_err(Into::into)
+  }
+
+  fn from_reader<D, R>(reader: R) -> Result<D, MessagingError>
+  where
+    D: for<'a> serde::Deserialize<'a>,
+    R: Read,
+  {
+    serde_json::from_reader(reader).map_err(Into::into)
+  }
 }
 
-/// Read message function with a generic [`Read`]er so that we can test it
-/// without having to actually use standard in/out.
-pub(crate) fn generic_read_message<D, R>(
+/// A [`Codec`] encoding messages as CBOR, for compact binary messages
+/// between two processes that both use this crate. Requires the `cbor`
+/// feature.
+#[cfg(feature = "cbor")]
+#[derive(Debug, Default)]
+pub struct Cbor;
+
+#[cfg(feature = "cbor")]
+impl Codec for Cbor {
+  fn to_vec<S>(message: &S) -> Result<Vec<u8>, MessagingError>
+  where
+    S: serde::Serialize,
+  {
+    serde_cbor::to_vec(message).map_err(Into::into)
+  }
+
+  fn from_reader<D, R>(reader: R) -> Result<D, MessagingError>
+  where
+    D: for<'a> serde::Deserialize<'a>,
+    R: Read,
+  {
+    serde_cbor::from_reader(reader).map_err(Into::into)
+  }
+}
+
+/// A [`Codec`] encoding messages as FlexBuffers, for compact binary
+/// messages between two processes that both use this crate. Requires the
+/// `flexbuffers` feature.
+#[cfg(feature = "flexbuffers")]
+#[derive(Debug, Default)]
+pub struct FlexBuffers;
+
+#[cfg(feature = "flexbuffers")]
+impl Codec for FlexBuffers {
+  fn to_vec<S>(message: &S) -> Result<Vec<u8>, MessagingError>
+  where
+    S: serde::Serialize,
+  {
+    flexbuffers::to_vec(message)
+      .map_err(|error| MessagingError::FlexBuffers(error.to_string()))
+  }
+
+  fn from_reader<D, R>(mut reader: R) -> Result<D, MessagingError>
+  where
+    D: for<'a> serde::Deserialize<'a>,
+    R: Read,
+  {
+    let mut message_bytes = vec![];
+    reader.read_to_end(&mut message_bytes)?;
+
+    flexbuffers::from_slice(&message_bytes)
+      .map_err(|error| MessagingError::FlexBuffers(error.to_string()))
+  }
+}
+
+/// Reads a message in the [native messaging] format from a generic
+/// [`Read`]er, so the framing can be reused over anything that isn't
+/// standard in, such as a socket, a pipe, or a subprocess's stdio. Always
+/// uses the [`Json`] codec; use [`read_message_from_with_codec`] for a
+/// different one. Messages longer than [`MAX_MESSAGE_LENGTH`] are
+/// rejected; use [`read_message_from_with_limit`] to change that.
+///
+/// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+pub fn read_message_from<D, R>(reader: R) -> Result<D, MessagingError>
+where
+  D: for<'a> serde::Deserialize<'a>,
+  R: Read,
+{
+  read_message_from_with_codec::<D, R, Json>(reader)
+}
+
+/// Like [`read_message_from`], but rejects messages longer than `limit`
+/// bytes instead of [`MAX_MESSAGE_LENGTH`]. Useful for embedders that need
+/// to raise or lower the cap, e.g. to match a specific browser or an
+/// internal process-to-process link.
+///
+/// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+pub fn read_message_from_with_limit<D, R>(
+  reader: R,
+  limit: u32,
+) -> Result<D, MessagingError>
+where
+  D: for<'a> serde::Deserialize<'a>,
+  R: Read,
+{
+  read_message_from_with_codec_and_limit::<D, R, Json>(reader, limit)
+}
+
+/// Like [`read_message_from`], but using the codec `C` instead of
+/// [`Json`], so two cooperating native-messaging processes can exchange
+/// messages in a more compact binary format.
+///
+/// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+pub fn read_message_from_with_codec<D, R, C>(
+  reader: R,
+) -> Result<D, MessagingError>
+where
+  D: for<'a> serde::Deserialize<'a>,
+  R: Read,
+  C: Codec,
+{
+  read_message_from_with_codec_and_limit::<D, R, C>(reader, MAX_MESSAGE_LENGTH)
+}
+
+/// Combines [`read_message_from_with_codec`] and
+/// [`read_message_from_with_limit`]: reads using the codec `C`, rejecting
+/// messages longer than `limit` bytes.
+///
+/// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+pub fn read_message_from_with_codec_and_limit<D, R, C>(
   mut reader: R,
+  limit: u32,
 ) -> Result<D, MessagingError>
 where
   D: for<'a> serde::Deserialize<'a>,
   R: Read,
+  C: Codec,
 {
-  let message_length = reader.read_u32::<NativeEndian>()?.try_into()?;
-  let message_bytes = reader.take(message_length);
+  let message_length = reader.read_u32::<NativeEndian>()?;
+  if message_length > limit {
+    return Err(MessagingError::MessageTooLarge {
+      length: message_length,
+      limit,
+    });
+  }
 
-  serde_json::from_reader(message_bytes).map_err(Into::into)
+  let message_bytes = reader.take(message_length.into());
+
+  C::from_reader(message_bytes)
 }
 
 /// Attempts to read a message from the program's stdin in the
@@ -101,21 +332,90 @@ where
 {
   let stdin = std::io::stdin();
   let stdin = stdin.lock();
-  generic_read_message(stdin)
+  read_message_from(stdin)
+}
+
+/// Writes a message in the [native messaging] format to a generic
+/// [`Write`]r, so the framing can be reused over anything that isn't
+/// standard out, such as a socket, a pipe, or a subprocess's stdio. Always
+/// uses the [`Json`] codec; use [`write_message_to_with_codec`] for a
+/// different one. Messages longer than [`MAX_MESSAGE_LENGTH`] are
+/// rejected; use [`write_message_to_with_limit`] to change that.
+///
+/// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+pub fn write_message_to<S, W>(
+  message: &S,
+  writer: W,
+) -> Result<(), MessagingError>
+where
+  S: serde::Serialize,
+  W: Write,
+{
+  write_message_to_with_codec::<S, W, Json>(message, writer)
+}
+
+/// Like [`write_message_to`], but rejects messages longer than `limit`
+/// bytes instead of [`MAX_MESSAGE_LENGTH`]. Useful for embedders that need
+/// to raise or lower the cap, e.g. to match a specific browser or an
+/// internal process-to-process link.
+///
+/// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+pub fn write_message_to_with_limit<S, W>(
+  message: &S,
+  writer: W,
+  limit: u32,
+) -> Result<(), MessagingError>
+where
+  S: serde::Serialize,
+  W: Write,
+{
+  write_message_to_with_codec_and_limit::<S, W, Json>(message, writer, limit)
 }
 
-/// Write message function with a generic [`Write`]r so that we can test it
-/// without having to actually use standard in/out.
-pub(crate) fn generic_write_message<S, W>(
+/// Like [`write_message_to`], but using the codec `C` instead of
+/// [`Json`], so two cooperating native-messaging processes can exchange
+/// messages in a more compact binary format.
+///
+/// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+pub fn write_message_to_with_codec<S, W, C>(
+  message: &S,
+  writer: W,
+) -> Result<(), MessagingError>
+where
+  S: serde::Serialize,
+  W: Write,
+  C: Codec,
+{
+  write_message_to_with_codec_and_limit::<S, W, C>(
+    message,
+    writer,
+    MAX_MESSAGE_LENGTH,
+  )
+}
+
+/// Combines [`write_message_to_with_codec`] and
+/// [`write_message_to_with_limit`]: writes using the codec `C`, rejecting
+/// messages longer than `limit` bytes.
+///
+/// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+pub fn write_message_to_with_codec_and_limit<S, W, C>(
   message: &S,
   mut writer: W,
+  limit: u32,
 ) -> Result<(), MessagingError>
 where
   S: serde::Serialize,
   W: Write,
+  C: Codec,
 {
-  let message_bytes = serde_json::to_vec(message)?;
-  let message_length = message_bytes.len().try_into()?;
+  let message_bytes = C::to_vec(message)?;
+  let message_length: u32 = message_bytes.len().try_into()?;
+  if message_length > limit {
+    return Err(MessagingError::MessageTooLarge {
+      length: message_length,
+      limit,
+    });
+  }
 
   writer.write_u32::<NativeEndian>(message_length)?;
   writer.write_all(&message_bytes)?;
@@ -132,12 +432,255 @@ where
 {
   let stdout = std::io::stdout();
   let stdout = stdout.lock();
-  generic_write_message(message, stdout)
+  write_message_to(message, stdout)
+}
+
+/// Re-exported so [`send!`] and [`send_to!`] can build a [`serde_json::Value`]
+/// without requiring callers to depend on `serde_json` themselves.
+pub use serde_json;
+
+/// Builds a JSON value with [`serde_json::json!`] and writes it to the
+/// program's stdout as a message, for quick ad-hoc replies (e.g. status or
+/// error objects) that don't warrant a dedicated struct.
+///
+/// ```rust,no_run
+/// use web_ext_native_messaging::send;
+///
+/// send!({ "status": "ok" }).unwrap();
+/// ```
+#[macro_export]
+macro_rules! send {
+  ($($json:tt)+) => {
+    $crate::write_message(&$crate::serde_json::json!($($json)+))
+  };
+}
+
+/// Like [`send!`], but writes to a given [`Write`]r via
+/// [`write_message_to`] instead of stdout.
+///
+/// ```rust,no_run
+/// use web_ext_native_messaging::send_to;
+///
+/// let mut buffer = Vec::new();
+/// send_to!(&mut buffer, { "status": "ok" }).unwrap();
+/// ```
+#[macro_export]
+macro_rules! send_to {
+  ($writer:expr, $($json:tt)+) => {
+    $crate::write_message_to(&$crate::serde_json::json!($($json)+), $writer)
+  };
+}
+
+/// Reads the 4-byte length prefix off of `reader`, distinguishing a clean
+/// EOF (no bytes read yet) from a real error (EOF after some, but not all,
+/// of the length bytes were read).
+fn read_message_length<R>(reader: &mut R) -> Result<Option<u32>, MessagingError>
+where
+  R: Read,
+{
+  let mut length_bytes = [0; 4];
+  let mut bytes_read = 0;
+
+  while bytes_read < length_bytes.len() {
+    match reader.read(&mut length_bytes[bytes_read..])? {
+      0 if bytes_read == 0 => return Ok(None),
+      0 => {
+        return Err(MessagingError::Io(std::io::Error::from(
+          std::io::ErrorKind::UnexpectedEof,
+        )))
+      }
+      read => bytes_read += read,
+    }
+  }
+
+  Ok(Some(u32::from_ne_bytes(length_bytes)))
+}
+
+/// An iterator over messages read from a [`Read`]er, yielding `None` once
+/// the stream closes cleanly at a message boundary.
+///
+/// See [`messages`] and [`messages_from`].
+pub struct Messages<D, R> {
+  /// The underlying reader messages are framed off of.
+  reader: R,
+  /// Ties the iterator to the message type `D` without actually storing one.
+  _message: std::marker::PhantomData<D>,
+}
+
+impl<D, R> Iterator for Messages<D, R>
+where
+  D: for<'a> serde::Deserialize<'a>,
+  R: Read,
+{
+  type Item = Result<D, MessagingError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let message_length = match read_message_length(&mut self.reader) {
+      Ok(Some(length)) => length,
+      Ok(None) => return None,
+      Err(error) => return Some(Err(error)),
+    };
+
+    if message_length > MAX_MESSAGE_LENGTH {
+      return Some(Err(MessagingError::MessageTooLarge {
+        length: message_length,
+        limit: MAX_MESSAGE_LENGTH,
+      }));
+    }
+
+    let message_bytes = (&mut self.reader).take(message_length.into());
+    Some(serde_json::from_reader(message_bytes).map_err(Into::into))
+  }
+}
+
+/// Returns an iterator over messages read from `reader` in the
+/// [native messaging] format, ending once the stream closes cleanly at a
+/// message boundary.
+///
+/// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+pub fn messages_from<D, R>(reader: R) -> Messages<D, R>
+where
+  D: for<'a> serde::Deserialize<'a>,
+  R: Read,
+{
+  Messages {
+    reader,
+    _message: std::marker::PhantomData,
+  }
+}
+
+/// Returns an iterator over messages read from the program's stdin in the
+/// [native messaging] format, ending once the extension disconnects.
+///
+/// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+pub fn messages<D>() -> Messages<D, std::io::Stdin>
+where
+  D: for<'a> serde::Deserialize<'a>,
+{
+  messages_from(std::io::stdin())
 }
 
+/// Async (Tokio) variants of [`read_message`]/[`write_message`], gated
+/// behind the `async` feature.
+#[cfg(feature = "async")]
+mod async_io {
+  use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+  use crate::MessagingError;
+
+  /// Reads a native-endian `u32` off of `reader`. Tokio's `AsyncReadExt`
+  /// only has `_le`/`_be` integer readers, not a native-endian one, so the
+  /// 4 bytes are read and converted manually to mirror the sync path's use
+  /// of `byteorder::NativeEndian`.
+  async fn read_u32_native_endian<R>(reader: &mut R) -> Result<u32, MessagingError>
+  where
+    R: AsyncRead + Unpin,
+  {
+    let mut length_bytes = [0; 4];
+    reader.read_exact(&mut length_bytes).await?;
+    Ok(u32::from_ne_bytes(length_bytes))
+  }
+
+  /// Writes `value` to `writer` as a native-endian `u32`, mirroring the
+  /// sync path's use of `byteorder::NativeEndian`.
+  async fn write_u32_native_endian<W>(
+    writer: &mut W,
+    value: u32,
+  ) -> Result<(), MessagingError>
+  where
+    W: AsyncWrite + Unpin,
+  {
+    writer.write_all(&value.to_ne_bytes()).await?;
+    Ok(())
+  }
+
+  /// Reads a message in the [native messaging] format from a generic
+  /// [`AsyncRead`]er. Messages longer than [`crate::MAX_MESSAGE_LENGTH`]
+  /// are rejected before the message buffer is allocated.
+  ///
+  /// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+  pub async fn async_read_message_from<D, R>(
+    mut reader: R,
+  ) -> Result<D, MessagingError>
+  where
+    D: for<'a> serde::Deserialize<'a>,
+    R: AsyncRead + Unpin,
+  {
+    let message_length = read_u32_native_endian(&mut reader).await?;
+    if message_length > crate::MAX_MESSAGE_LENGTH {
+      return Err(MessagingError::MessageTooLarge {
+        length: message_length,
+        limit: crate::MAX_MESSAGE_LENGTH,
+      });
+    }
+
+    let mut message_bytes = vec![0; message_length as usize];
+    reader.read_exact(&mut message_bytes).await?;
+
+    serde_json::from_slice(&message_bytes).map_err(Into::into)
+  }
+
+  /// Attempts to read a message from the program's stdin in the
+  /// [native messaging] format.
+  ///
+  /// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+  pub async fn async_read_message<D>() -> Result<D, MessagingError>
+  where
+    D: for<'a> serde::Deserialize<'a>,
+  {
+    async_read_message_from(tokio::io::stdin()).await
+  }
+
+  /// Writes a message in the [native messaging] format to a generic
+  /// [`AsyncWrite`]r. Messages longer than [`crate::MAX_MESSAGE_LENGTH`]
+  /// are rejected before the length prefix is written.
+  ///
+  /// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+  pub async fn async_write_message_to<S, W>(
+    message: &S,
+    mut writer: W,
+  ) -> Result<(), MessagingError>
+  where
+    S: serde::Serialize,
+    W: AsyncWrite + Unpin,
+  {
+    let message_bytes = serde_json::to_vec(message)?;
+    let message_length: u32 = message_bytes.len().try_into()?;
+    if message_length > crate::MAX_MESSAGE_LENGTH {
+      return Err(MessagingError::MessageTooLarge {
+        length: message_length,
+        limit: crate::MAX_MESSAGE_LENGTH,
+      });
+    }
+
+    write_u32_native_endian(&mut writer, message_length).await?;
+    writer.write_all(&message_bytes).await?;
+    writer.flush().await.map_err(MessagingError::Io)
+  }
+
+  /// Attempts to write a message to the program's stdout in the
+  /// [native messaging] format.
+  ///
+  /// [native messaging]: https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#app_side
+  pub async fn async_write_message<S>(message: &S) -> Result<(), MessagingError>
+  where
+    S: serde::Serialize,
+  {
+    async_write_message_to(message, tokio::io::stdout()).await
+  }
+}
+
+#[cfg(feature = "async")]
+pub use async_io::{
+  async_read_message,
+  async_read_message_from,
+  async_write_message,
+  async_write_message_to,
+};
+
 #[cfg(test)]
 pub(crate) mod tests {
-  use crate::{generic_read_message, generic_write_message, MessagingError};
+  use crate::{messages_from, read_message_from, write_message_to, MessagingError};
 
   #[derive(Debug, serde::Deserialize, serde::Serialize, PartialEq)]
   struct Message {
@@ -155,13 +698,158 @@ pub(crate) mod tests {
     let mut buffer: Vec<u8> = vec![];
 
     // Write the message to the buffer.
-    generic_write_message(&test_message, &mut buffer)?;
+    write_message_to(&test_message, &mut buffer)?;
 
     // Then read the message, we get `std::io::Read` by dereferencing the
     // `Vec<u8>` to `&[u8]`.
-    let message = generic_read_message::<Message, _>(&*buffer)?;
+    let message = read_message_from::<Message, _>(&*buffer)?;
 
     assert_eq!(message, test_message);
     Ok(())
   }
+
+  #[test]
+  fn test_messages_ends_cleanly_on_eof() -> Result<(), MessagingError> {
+    let first_message = Message {
+      text: "First".to_string(),
+    };
+    let second_message = Message {
+      text: "Second".to_string(),
+    };
+
+    let mut buffer: Vec<u8> = vec![];
+    write_message_to(&first_message, &mut buffer)?;
+    write_message_to(&second_message, &mut buffer)?;
+
+    let received: Result<Vec<Message>, MessagingError> =
+      messages_from(&*buffer).collect();
+
+    assert_eq!(received?, vec![first_message, second_message]);
+    Ok(())
+  }
+
+  #[test]
+  fn test_write_message_rejects_oversized_messages() {
+    let oversized_message = Message {
+      text: "x".repeat(64),
+    };
+
+    let mut buffer: Vec<u8> = vec![];
+    let result =
+      crate::write_message_to_with_limit(&oversized_message, &mut buffer, 8);
+
+    assert!(matches!(
+      result,
+      Err(MessagingError::MessageTooLarge { limit: 8, .. })
+    ));
+  }
+
+  #[test]
+  fn test_messages_rejects_oversized_messages() {
+    // A hand-built frame claiming a length larger than `MAX_MESSAGE_LENGTH`.
+    let mut buffer: Vec<u8> = (crate::MAX_MESSAGE_LENGTH + 1)
+      .to_ne_bytes()
+      .to_vec();
+    buffer.extend_from_slice(b"{}");
+
+    let mut iterator = messages_from::<Message, _>(&*buffer);
+    let result = iterator.next();
+
+    assert!(matches!(
+      result,
+      Some(Err(MessagingError::MessageTooLarge { .. }))
+    ));
+  }
+
+  #[test]
+  fn test_send_to() -> Result<(), MessagingError> {
+    let mut buffer: Vec<u8> = vec![];
+    crate::send_to!(&mut buffer, { "status": "ok" })?;
+
+    let message: serde_json::Value = read_message_from(&*buffer)?;
+
+    assert_eq!(message, serde_json::json!({ "status": "ok" }));
+    Ok(())
+  }
+
+  #[cfg(feature = "cbor")]
+  #[test]
+  fn test_cbor_codec() -> Result<(), MessagingError> {
+    use crate::{read_message_from_with_codec, write_message_to_with_codec, Cbor};
+
+    let test_message = Message {
+      text: "This is a test".to_string(),
+    };
+
+    let mut buffer: Vec<u8> = vec![];
+    write_message_to_with_codec::<_, _, Cbor>(&test_message, &mut buffer)?;
+    let message = read_message_from_with_codec::<Message, _, Cbor>(&*buffer)?;
+
+    assert_eq!(message, test_message);
+    Ok(())
+  }
+
+  #[cfg(feature = "flexbuffers")]
+  #[test]
+  fn test_flexbuffers_codec() -> Result<(), MessagingError> {
+    use crate::{
+      read_message_from_with_codec,
+      write_message_to_with_codec,
+      FlexBuffers,
+    };
+
+    let test_message = Message {
+      text: "This is a test".to_string(),
+    };
+
+    let mut buffer: Vec<u8> = vec![];
+    write_message_to_with_codec::<_, _, FlexBuffers>(&test_message, &mut buffer)?;
+    let message =
+      read_message_from_with_codec::<Message, _, FlexBuffers>(&*buffer)?;
+
+    assert_eq!(message, test_message);
+    Ok(())
+  }
+
+  #[cfg(feature = "async")]
+  #[tokio::test]
+  async fn test_async_messaging() -> Result<(), MessagingError> {
+    use crate::{async_read_message_from, async_write_message_to};
+
+    let test_message = Message {
+      text: "This is a test".to_string(),
+    };
+
+    // Create a buffer that will act as both the reader and writer
+    // (i.e. stdin and stdout).
+    let mut buffer: Vec<u8> = vec![];
+
+    // Write the message to the buffer.
+    async_write_message_to(&test_message, &mut buffer).await?;
+
+    // Then read the message back out of the buffer.
+    let message = async_read_message_from::<Message, _>(&*buffer).await?;
+
+    assert_eq!(message, test_message);
+    Ok(())
+  }
+
+  #[cfg(feature = "async")]
+  #[tokio::test]
+  async fn test_async_read_message_rejects_oversized_messages() {
+    use crate::async_read_message_from;
+
+    // A hand-built frame claiming a length larger than `MAX_MESSAGE_LENGTH`.
+    let mut buffer: Vec<u8> = (crate::MAX_MESSAGE_LENGTH + 1)
+      .to_ne_bytes()
+      .to_vec();
+    buffer.extend_from_slice(b"{}");
+
+    let result = async_read_message_from::<Message, _>(&*buffer).await;
+
+    assert!(matches!(
+      result,
+      Err(MessagingError::MessageTooLarge { .. })
+    ));
+  }
 }